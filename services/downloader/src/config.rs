@@ -10,6 +10,17 @@ pub struct Config {
     pub download_dir: String,
     pub max_concurrent: usize,
     pub cookies_path: String,
+    pub ytdlp: YtdlpConfig,
+    /// How many times to retry a download after a transient yt-dlp failure
+    /// (rate limiting, network blips) before giving up.
+    pub max_retries: u32,
+    /// When true, fetch the latest yt-dlp release into `download_dir` on
+    /// startup instead of relying on whatever is already on `PATH`.
+    pub auto_update_ytdlp: bool,
+    /// Maximum time a single yt-dlp invocation may run before it's killed
+    /// and treated as a failure, so a stuck process can't hold a
+    /// concurrency permit forever.
+    pub download_timeout_secs: u64,
 }
 
 impl Config {
@@ -25,6 +36,55 @@ impl Config {
                 .parse()
                 .unwrap_or(3),
             cookies_path: env::var("COOKIES_PATH").unwrap_or_else(|_| "/app/cookies.txt".into()),
+            ytdlp: YtdlpConfig::from_env(),
+            max_retries: env::var("MAX_RETRIES")
+                .unwrap_or_else(|_| "3".into())
+                .parse()
+                .unwrap_or(3),
+            auto_update_ytdlp: env::var("AUTO_UPDATE_YTDLP")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            download_timeout_secs: env::var("DOWNLOAD_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "3600".into())
+                .parse()
+                .unwrap_or(3600),
+        }
+    }
+}
+
+/// Knobs for the yt-dlp invocation itself, so operators can point at a
+/// different executable, change quality/format, or target non-YouTube
+/// sites without recompiling.
+pub struct YtdlpConfig {
+    pub executable_path: String,
+    pub format: String,
+    pub merge_output_format: Option<String>,
+    /// JS runtime yt-dlp shells out to for sites needing JS interpretation
+    /// (e.g. `node`, `deno`). `None` leaves it to yt-dlp's own `--js-runtimes`
+    /// default/autodetection instead of forcing one.
+    pub js_runtime: Option<String>,
+    pub extra_args: Vec<String>,
+}
+
+impl YtdlpConfig {
+    pub fn from_env() -> Self {
+        Self {
+            executable_path: env::var("YTDLP_EXECUTABLE_PATH").unwrap_or_else(|_| "yt-dlp".into()),
+            format: env::var("YTDLP_FORMAT")
+                .unwrap_or_else(|_| "bestvideo[height<=1080]+bestaudio/best[height<=1080]".into()),
+            merge_output_format: match env::var("YTDLP_MERGE_OUTPUT_FORMAT") {
+                Ok(v) if v.is_empty() => None,
+                Ok(v) => Some(v),
+                Err(_) => Some("mp4".into()),
+            },
+            js_runtime: match env::var("YTDLP_JS_RUNTIME") {
+                Ok(v) if v.is_empty() => None,
+                Ok(v) => Some(v),
+                Err(_) => Some("node".into()),
+            },
+            extra_args: env::var("YTDLP_EXTRA_ARGS")
+                .map(|v| v.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
         }
     }
 }
@@ -44,24 +104,65 @@ mod tests {
         env::remove_var("DOWNLOAD_DIR");
         env::remove_var("MAX_CONCURRENT_DOWNLOADS");
         env::remove_var("COOKIES_PATH");
+        env::remove_var("YTDLP_EXECUTABLE_PATH");
+        env::remove_var("YTDLP_FORMAT");
+        env::remove_var("YTDLP_MERGE_OUTPUT_FORMAT");
+        env::remove_var("YTDLP_JS_RUNTIME");
+        env::remove_var("YTDLP_EXTRA_ARGS");
+        env::remove_var("MAX_RETRIES");
+        env::remove_var("AUTO_UPDATE_YTDLP");
+        env::remove_var("DOWNLOAD_TIMEOUT_SECS");
 
         let config = Config::from_env();
         assert_eq!(config.nats_url, "nats://localhost:4222");
         assert_eq!(config.download_dir, "/tmp/downloads");
         assert_eq!(config.max_concurrent, 3);
         assert_eq!(config.cookies_path, "/app/cookies.txt");
+        assert_eq!(config.max_retries, 3);
+        assert!(!config.auto_update_ytdlp);
+        assert_eq!(config.download_timeout_secs, 3600);
+        assert_eq!(config.ytdlp.executable_path, "yt-dlp");
+        assert_eq!(
+            config.ytdlp.format,
+            "bestvideo[height<=1080]+bestaudio/best[height<=1080]"
+        );
+        assert_eq!(config.ytdlp.merge_output_format, Some("mp4".into()));
+        assert_eq!(config.ytdlp.js_runtime, Some("node".into()));
+        assert!(config.ytdlp.extra_args.is_empty());
 
         // --- Test 2: reads custom env vars ---
         env::set_var("NATS_URL", "nats://custom:9999");
         env::set_var("DOWNLOAD_DIR", "/custom/downloads");
         env::set_var("MAX_CONCURRENT_DOWNLOADS", "5");
         env::set_var("COOKIES_PATH", "/custom/cookies.txt");
+        env::set_var("YTDLP_EXECUTABLE_PATH", "/opt/yt-dlp");
+        env::set_var("YTDLP_FORMAT", "bestaudio/best");
+        env::set_var("YTDLP_MERGE_OUTPUT_FORMAT", "");
+        env::set_var("YTDLP_JS_RUNTIME", "deno");
+        env::set_var("YTDLP_EXTRA_ARGS", "--no-check-certificate --geo-bypass");
+        env::set_var("MAX_RETRIES", "5");
+        env::set_var("AUTO_UPDATE_YTDLP", "true");
+        env::set_var("DOWNLOAD_TIMEOUT_SECS", "600");
 
         let config = Config::from_env();
         assert_eq!(config.nats_url, "nats://custom:9999");
         assert_eq!(config.download_dir, "/custom/downloads");
         assert_eq!(config.max_concurrent, 5);
         assert_eq!(config.cookies_path, "/custom/cookies.txt");
+        assert_eq!(config.max_retries, 5);
+        assert!(config.auto_update_ytdlp);
+        assert_eq!(config.download_timeout_secs, 600);
+        assert_eq!(config.ytdlp.executable_path, "/opt/yt-dlp");
+        assert_eq!(config.ytdlp.format, "bestaudio/best");
+        assert_eq!(config.ytdlp.merge_output_format, None);
+        assert_eq!(config.ytdlp.js_runtime, Some("deno".into()));
+        assert_eq!(
+            config.ytdlp.extra_args,
+            vec![
+                "--no-check-certificate".to_string(),
+                "--geo-bypass".to_string()
+            ]
+        );
 
         // --- Test 3: invalid number falls back to default ---
         env::set_var("MAX_CONCURRENT_DOWNLOADS", "not_a_number");
@@ -73,5 +174,13 @@ mod tests {
         env::remove_var("DOWNLOAD_DIR");
         env::remove_var("MAX_CONCURRENT_DOWNLOADS");
         env::remove_var("COOKIES_PATH");
+        env::remove_var("YTDLP_EXECUTABLE_PATH");
+        env::remove_var("YTDLP_FORMAT");
+        env::remove_var("YTDLP_MERGE_OUTPUT_FORMAT");
+        env::remove_var("YTDLP_JS_RUNTIME");
+        env::remove_var("YTDLP_EXTRA_ARGS");
+        env::remove_var("MAX_RETRIES");
+        env::remove_var("AUTO_UPDATE_YTDLP");
+        env::remove_var("DOWNLOAD_TIMEOUT_SECS");
     }
 }
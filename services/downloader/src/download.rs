@@ -7,9 +7,91 @@ use anyhow::{Context, Result};
 use log::{error, info, warn};
 use serde::Deserialize;
 use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use tokio::process::Command;
 
 use crate::config::Config;
+use crate::models::{DownloadProgress, DownloadScheduled};
+
+/// Minimum time between `download.progress` publishes for the same video,
+/// so a fast connection doesn't flood NATS with one message per fragment.
+const PROGRESS_THROTTLE: Duration = Duration::from_secs(1);
+
+/// Marks the progress lines we ask yt-dlp to print via `--progress-template`,
+/// so we can tell them apart from yt-dlp's regular log output on stdout.
+const PROGRESS_PREFIX: &str = "YAGAMI_PROGRESS|";
+
+/// Grace period past the reported start time before we kick off the real
+/// download — yt-dlp's own availability check tends to lag the scheduled
+/// time by a few seconds.
+const SCHEDULED_START_GRACE: Duration = Duration::from_secs(5);
+
+/// How long the schedule probe (`--dump-json --skip-download`) may run
+/// before we give up on it — much shorter than a real download's timeout,
+/// since it does no transferring and a hang here means yt-dlp itself is
+/// stuck, not that the network is slow.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Classified yt-dlp failure, so `process_download` can tell a transient
+/// hiccup (worth retrying) from a permanent one (isn't).
+#[derive(Debug, thiserror::Error)]
+pub enum DownloadError {
+    #[error("rate limited by host: {0}")]
+    RateLimited(String),
+    #[error("network error: {0}")]
+    Network(String),
+    #[error("geo-blocked: {0}")]
+    GeoBlocked(String),
+    #[error("video unavailable: {0}")]
+    Unavailable(String),
+    #[error("yt-dlp failed: {0}")]
+    Fatal(String),
+    #[error("yt-dlp timed out")]
+    TimedOut,
+}
+
+impl DownloadError {
+    /// Classify yt-dlp's stderr into a variant by matching the substrings
+    /// it's known to emit for each failure mode.
+    fn classify(stderr: &str) -> Self {
+        let message: String = stderr.chars().take(300).collect();
+        if stderr.contains("HTTP Error 429") {
+            DownloadError::RateLimited(message)
+        } else if stderr.contains("Temporary failure") || stderr.contains("HTTP Error 5") {
+            DownloadError::Network(message)
+        } else if stderr.contains("available in your country") {
+            DownloadError::GeoBlocked(message)
+        } else if stderr.contains("Sign in to confirm") || stderr.contains("Video unavailable") {
+            DownloadError::Unavailable(message)
+        } else {
+            DownloadError::Fatal(message)
+        }
+    }
+
+    /// Whether retrying is worth it — rate limiting, network blips and
+    /// timeouts usually clear up on their own, geo-blocks and removed
+    /// videos don't.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            DownloadError::RateLimited(_) | DownloadError::Network(_) | DownloadError::TimedOut
+        )
+    }
+}
+
+impl From<anyhow::Error> for DownloadError {
+    fn from(e: anyhow::Error) -> Self {
+        DownloadError::Fatal(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for DownloadError {
+    fn from(e: std::io::Error) -> Self {
+        DownloadError::Fatal(e.to_string())
+    }
+}
 
 /// Metadata extracted from yt-dlp's .info.json file.
 #[derive(Debug, Default)]
@@ -38,28 +120,256 @@ struct YtdlpInfo {
     thumbnail: Option<String>,
 }
 
+/// Subset of yt-dlp's `--dump-json` output we need to detect upcoming
+/// livestreams/premieres before attempting an actual download.
+#[derive(Debug, Deserialize)]
+struct YtdlpProbeInfo {
+    live_status: Option<String>,
+    release_timestamp: Option<i64>,
+    availability: Option<String>,
+}
+
+/// Probe a video's metadata without downloading it, to find out whether
+/// it's an upcoming livestream/premiere yt-dlp can't download yet.
+///
+/// yt-dlp doesn't always have a clean `release_timestamp` for an upcoming
+/// video — sometimes the only indication is the countdown text it prints
+/// ("This live event will begin in 2 hours.", "Premieres in 45 minutes"),
+/// either alongside a successful JSON dump or on its own when the dump
+/// itself fails because the video isn't downloadable yet. We fall back to
+/// parsing that text whenever the JSON doesn't give us a timestamp.
+///
+/// Probe failures aren't treated as fatal here — they're swallowed and we
+/// fall through to a normal download attempt, which will surface the real
+/// error if there is one.
+async fn probe_scheduled_start(video_id: &str, url: &str, config: &Config) -> Option<i64> {
+    let mut args = vec![
+        "--dump-json".to_string(),
+        "--skip-download".to_string(),
+        "--no-playlist".to_string(),
+    ];
+
+    // Member-only/login-gated upcoming premieres need cookies to probe at
+    // all — without this they fail here and fall straight through to a
+    // real (also doomed) download attempt instead of waiting it out.
+    let cookies_path = PathBuf::from(&config.cookies_path);
+    if cookies_path.exists() {
+        args.push("--cookies".to_string());
+        args.push(config.cookies_path.clone());
+    }
+
+    args.push(url.to_string());
+
+    let mut child = Command::new(&config.ytdlp.executable_path)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    let mut stdout = child.stdout.take().expect("stdout was piped");
+    let mut stderr = child.stderr.take().expect("stderr was piped");
+
+    // Drain both pipes concurrently with waiting on the child, so a probe
+    // that writes more than fits in the pipe buffer can't deadlock against
+    // the timeout below.
+    let stdout_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf).await;
+        buf
+    });
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        let _ = stderr.read_to_end(&mut buf).await;
+        buf
+    });
+
+    let status = match tokio::time::timeout(PROBE_TIMEOUT, child.wait()).await {
+        Ok(result) => result.ok()?,
+        Err(_) => {
+            warn!(
+                "Schedule probe timed out for {} after {:?}, killing process",
+                video_id, PROBE_TIMEOUT
+            );
+            let _ = child.kill().await;
+            stdout_task.abort();
+            stderr_task.abort();
+            return None;
+        }
+    };
+
+    let stdout_buf = stdout_task.await.unwrap_or_default();
+    let stderr_buf = String::from_utf8_lossy(&stderr_task.await.unwrap_or_default()).into_owned();
+
+    if !status.success() {
+        // yt-dlp can refuse to probe an upcoming stream at all, but still
+        // print the countdown as its error reason — that's still useful.
+        if let Some(start) = parse_countdown_text(&stderr_buf) {
+            return Some(start);
+        }
+        warn!("Schedule probe failed for {}: {}", video_id, stderr_buf);
+        return None;
+    }
+
+    let info: YtdlpProbeInfo = match serde_json::from_slice(&stdout_buf) {
+        Ok(i) => i,
+        Err(e) => {
+            warn!(
+                "Failed to parse schedule probe JSON for {}: {}",
+                video_id, e
+            );
+            return None;
+        }
+    };
+
+    let is_upcoming = info.live_status.as_deref() == Some("is_upcoming")
+        || info.availability.as_deref() == Some("upcoming");
+    if !is_upcoming {
+        return None;
+    }
+
+    info.release_timestamp
+        .or_else(|| parse_countdown_text(&stderr_buf))
+}
+
+/// Parse yt-dlp's plain-text countdown ("This live event will begin in 2
+/// hours.", "Premieres in 45 minutes", "...in a few moments.") into an
+/// absolute unix timestamp. Returns `None` if no countdown phrase is found
+/// or its value can't be parsed.
+fn parse_countdown_text(text: &str) -> Option<i64> {
+    const MARKERS: [&str; 2] = ["will begin in ", "Premieres in "];
+    let (start, marker) = MARKERS.iter().find_map(|m| text.find(m).map(|i| (i, *m)))?;
+    let rest = text[start + marker.len()..].trim_start();
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs() as i64;
+
+    if rest.starts_with("a few moments") {
+        return Some(now + 60);
+    }
+
+    let mut tokens = rest.split_whitespace();
+    let amount: i64 = tokens.next()?.parse().ok()?;
+    let unit = tokens.next()?.trim_end_matches(['.', ',']);
+    let seconds = match unit {
+        "second" | "seconds" => amount,
+        "minute" | "minutes" => amount * 60,
+        "hour" | "hours" => amount * 3600,
+        "day" | "days" => amount * 86400,
+        _ => return None,
+    };
+
+    Some(now + seconds)
+}
+
+/// If `url` points at an upcoming livestream/premiere, publish a
+/// `download.scheduled` event and sleep until shortly after its start time.
+/// Returns immediately (no-op) for anything that's already downloadable.
+///
+/// Deliberately doesn't hold a semaphore permit while waiting — callers
+/// should acquire their permit only after this returns, so a scheduled
+/// stream doesn't sit on a concurrency slot for hours.
+pub async fn wait_for_scheduled_start(
+    video_id: &str,
+    title: &str,
+    url: &str,
+    config: &Config,
+    nats_client: &async_nats::Client,
+) {
+    let Some(start) = probe_scheduled_start(video_id, url, config).await else {
+        return;
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let delay = Duration::from_secs((start - now).max(0) as u64) + SCHEDULED_START_GRACE;
+
+    info!(
+        "{} is scheduled to start at {} (in {}s) — waiting before download",
+        video_id,
+        start,
+        delay.as_secs()
+    );
+    publish_scheduled(nats_client, video_id, title, start).await;
+
+    tokio::time::sleep(delay).await;
+}
+
+/// Publish a `download.scheduled` event to NATS. Failures are logged and
+/// swallowed — the worker still proceeds with the wait/download either way.
+async fn publish_scheduled(
+    nats_client: &async_nats::Client,
+    video_id: &str,
+    title: &str,
+    scheduled_start: i64,
+) {
+    let event = DownloadScheduled {
+        video_id: video_id.to_string(),
+        title: title.to_string(),
+        scheduled_start,
+    };
+    match serde_json::to_vec(&event) {
+        Ok(payload) => {
+            if let Err(e) = nats_client
+                .publish("download.scheduled", payload.into())
+                .await
+            {
+                warn!("Failed to publish download.scheduled: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize download.scheduled: {}", e),
+    }
+}
+
 /// Download a video using yt-dlp. Returns the file path and extracted metadata.
 ///
 /// LEARNING: `Result<DownloadOutput>` is short for `Result<DownloadOutput, anyhow::Error>`.
 /// The `?` operator propagates errors — if an expression returns Err,
 /// the function immediately returns that Err. No try/catch needed!
-pub async fn download_video(video_id: &str, url: &str, config: &Config) -> Result<DownloadOutput> {
+pub async fn download_video(
+    video_id: &str,
+    url: &str,
+    config: &Config,
+    format_override: Option<&str>,
+    audio_only: bool,
+    nats_client: &async_nats::Client,
+) -> Result<DownloadOutput, DownloadError> {
     let output_template = format!("{}/{}.%(ext)s", config.download_dir, video_id);
 
+    let format = if audio_only {
+        "bestaudio/best".to_string()
+    } else {
+        format_override
+            .map(String::from)
+            .unwrap_or_else(|| config.ytdlp.format.clone())
+    };
+
     // LEARNING: `let mut` declares a mutable variable. By default, all
     // variables in Rust are immutable (like `val` in Kotlin or `let` in Swift).
-    let mut args = vec![
-        "-f".to_string(),
-        "bestvideo[height<=1080]+bestaudio/best[height<=1080]".to_string(),
-        "--merge-output-format".to_string(),
-        "mp4".to_string(),
-        "-o".to_string(),
-        output_template,
-        "--no-playlist".to_string(),
-        "--write-info-json".to_string(),
-        "--js-runtimes".to_string(),
-        "node".to_string(),
-    ];
+    let mut args = vec!["-f".to_string(), format];
+
+    if let Some(merge_format) = &config.ytdlp.merge_output_format {
+        args.push("--merge-output-format".to_string());
+        args.push(merge_format.clone());
+    }
+
+    args.push("-o".to_string());
+    args.push(output_template);
+    args.push("--no-playlist".to_string());
+    args.push("--write-info-json".to_string());
+    if let Some(js_runtime) = &config.ytdlp.js_runtime {
+        args.push("--js-runtimes".to_string());
+        args.push(js_runtime.clone());
+    }
+    args.push("--newline".to_string());
+    args.push("--progress-template".to_string());
+    args.push(format!(
+        "download:{}%(progress.downloaded_bytes)s/%(progress.total_bytes)s/%(progress.speed)s/%(progress.eta)s",
+        PROGRESS_PREFIX
+    ));
+
+    args.extend(config.ytdlp.extra_args.iter().cloned());
 
     // Pass cookies directly — mounted read-write so yt-dlp can update rotated cookies
     let cookies_path = PathBuf::from(&config.cookies_path);
@@ -72,22 +382,123 @@ pub async fn download_video(video_id: &str, url: &str, config: &Config) -> Resul
 
     info!("Downloading {} with yt-dlp...", video_id);
 
-    let output = Command::new("yt-dlp")
+    // LEARNING: piping stdout lets us read yt-dlp's progress output line by
+    // line as it's produced, instead of buffering everything until exit
+    // (which is what `Command::output()` does).
+    let mut child = Command::new(&config.ytdlp.executable_path)
         .args(&args)
-        .output()
-        .await
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .context("Failed to spawn yt-dlp")?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("yt-dlp failed for {}: {}", video_id, stderr);
-        anyhow::bail!("yt-dlp exited with: {}", stderr.chars().take(200).collect::<String>());
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let mut stderr = child.stderr.take().expect("stderr was piped");
+
+    // Drain stderr concurrently so the process never blocks on a full pipe
+    // while we're busy reading stdout.
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf).await;
+        buf
+    });
+
+    // Drain progress lines concurrently too, so a stalled download (no more
+    // stdout, but also no exit) doesn't prevent the timeout below from
+    // ever being checked.
+    let video_id_owned = video_id.to_string();
+    let nats_client_owned = nats_client.clone();
+    let progress_task = tokio::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut last_emit: Option<Instant> = None;
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Some(progress) = parse_progress_line(&video_id_owned, &line) else {
+                continue;
+            };
+            let now = Instant::now();
+            let should_emit = match last_emit {
+                Some(t) => now.duration_since(t) >= PROGRESS_THROTTLE,
+                None => true,
+            };
+            if should_emit {
+                publish_progress(&nats_client_owned, &progress).await;
+                last_emit = Some(now);
+            }
+        }
+    });
+
+    let timeout = Duration::from_secs(config.download_timeout_secs);
+    let status = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(result) => result.context("Failed to wait for yt-dlp")?,
+        Err(_) => {
+            warn!(
+                "yt-dlp timed out for {} after {:?}, killing process",
+                video_id, timeout
+            );
+            let _ = child.kill().await;
+            progress_task.abort();
+            stderr_task.abort();
+            cleanup_partial_files(&config.download_dir, video_id);
+            return Err(DownloadError::TimedOut);
+        }
+    };
+
+    let _ = progress_task.await;
+    let stderr_output = stderr_task.await.unwrap_or_default();
+
+    if !status.success() {
+        error!("yt-dlp failed for {}: {}", video_id, stderr_output);
+        return Err(DownloadError::classify(&stderr_output));
     }
 
     let file_path = find_downloaded_file(&config.download_dir, video_id)?;
     let metadata = read_info_json(&config.download_dir, video_id);
 
-    Ok(DownloadOutput { file_path, metadata })
+    Ok(DownloadOutput {
+        file_path,
+        metadata,
+    })
+}
+
+/// Parse a `--progress-template` line into a `DownloadProgress`, or `None`
+/// if the line isn't one of ours (yt-dlp's regular log lines share stdout).
+fn parse_progress_line(video_id: &str, line: &str) -> Option<DownloadProgress> {
+    let rest = line.strip_prefix(PROGRESS_PREFIX)?;
+    let mut fields = rest.splitn(4, '/');
+    let downloaded_bytes = fields.next()?.parse::<u64>().ok();
+    let total_bytes = fields.next()?.parse::<u64>().ok();
+    let speed = fields.next()?.parse::<f64>().ok();
+    let eta = fields.next()?.parse::<u64>().ok();
+
+    let percent = match (downloaded_bytes, total_bytes) {
+        (Some(d), Some(t)) if t > 0 => Some(d as f64 / t as f64 * 100.0),
+        _ => None,
+    };
+
+    Some(DownloadProgress {
+        video_id: video_id.to_string(),
+        downloaded_bytes,
+        total_bytes,
+        percent,
+        speed,
+        eta,
+    })
+}
+
+/// Publish a progress update to NATS. Failures are logged and swallowed —
+/// a dropped progress event shouldn't abort the download.
+async fn publish_progress(nats_client: &async_nats::Client, progress: &DownloadProgress) {
+    match serde_json::to_vec(progress) {
+        Ok(payload) => {
+            if let Err(e) = nats_client
+                .publish("download.progress", payload.into())
+                .await
+            {
+                warn!("Failed to publish download.progress: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize download.progress: {}", e),
+    }
 }
 
 /// Find the file that yt-dlp created (we don't know the extension ahead of time).
@@ -105,6 +516,26 @@ fn find_downloaded_file(dir: &str, video_id: &str) -> Result<String> {
     anyhow::bail!("Downloaded file not found for {}", video_id)
 }
 
+/// Remove any partial files a killed download left behind (the final file,
+/// fragment parts, and the `.info.json`), so a retry starts clean.
+fn cleanup_partial_files(dir: &str, video_id: &str) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with(video_id) {
+            if let Err(e) = std::fs::remove_file(entry.path()) {
+                warn!(
+                    "Failed to remove partial file {}: {}",
+                    entry.path().display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
 /// Get video metadata (file size) without downloading.
 pub fn get_file_size(path: &str) -> Result<u64> {
     let metadata = std::fs::metadata(path).context("Failed to read file metadata")?;
@@ -182,6 +613,44 @@ mod tests {
     use std::fs;
     use std::io::Write;
 
+    #[test]
+    fn test_parse_countdown_text_hours() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let start =
+            parse_countdown_text("ERROR: [youtube] abc123: This live event will begin in 2 hours.")
+                .unwrap();
+        assert!((start - (now + 7200)).abs() <= 2);
+    }
+
+    #[test]
+    fn test_parse_countdown_text_premieres_in_minutes() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let start =
+            parse_countdown_text("ERROR: [youtube] abc123: Premieres in 45 minutes").unwrap();
+        assert!((start - (now + 2700)).abs() <= 2);
+    }
+
+    #[test]
+    fn test_parse_countdown_text_a_few_moments() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let start = parse_countdown_text("This live event will begin in a few moments.").unwrap();
+        assert!((start - (now + 60)).abs() <= 2);
+    }
+
+    #[test]
+    fn test_parse_countdown_text_no_match() {
+        assert!(parse_countdown_text("ERROR: Video unavailable").is_none());
+    }
+
     #[test]
     fn test_get_file_size() {
         let dir = tempfile::tempdir().unwrap();
@@ -217,6 +686,94 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_progress_line_valid() {
+        let line = "YAGAMI_PROGRESS|1024/4096/512.5/6";
+        let progress = parse_progress_line("abc123", line).unwrap();
+        assert_eq!(progress.video_id, "abc123");
+        assert_eq!(progress.downloaded_bytes, Some(1024));
+        assert_eq!(progress.total_bytes, Some(4096));
+        assert_eq!(progress.speed, Some(512.5));
+        assert_eq!(progress.eta, Some(6));
+        assert_eq!(progress.percent, Some(25.0));
+    }
+
+    #[test]
+    fn test_parse_progress_line_unknown_total() {
+        let line = "YAGAMI_PROGRESS|1024/NA/NA/NA";
+        let progress = parse_progress_line("abc123", line).unwrap();
+        assert_eq!(progress.downloaded_bytes, Some(1024));
+        assert_eq!(progress.total_bytes, None);
+        assert_eq!(progress.percent, None);
+    }
+
+    #[test]
+    fn test_parse_progress_line_ignores_unrelated_output() {
+        let line = "[youtube] abc123: Downloading webpage";
+        assert!(parse_progress_line("abc123", line).is_none());
+    }
+
+    #[test]
+    fn test_classify_rate_limited() {
+        let err = DownloadError::classify("ERROR: HTTP Error 429: Too Many Requests");
+        assert!(matches!(err, DownloadError::RateLimited(_)));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_network() {
+        let err = DownloadError::classify(
+            "urlopen error [Errno -3] Temporary failure in name resolution",
+        );
+        assert!(matches!(err, DownloadError::Network(_)));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_geo_blocked() {
+        let err = DownloadError::classify(
+            "ERROR: The uploader has not made this video available in your country",
+        );
+        assert!(matches!(err, DownloadError::GeoBlocked(_)));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_unavailable() {
+        let err = DownloadError::classify("ERROR: Sign in to confirm you're not a bot");
+        assert!(matches!(err, DownloadError::Unavailable(_)));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_fatal_fallback() {
+        let err = DownloadError::classify("ERROR: unsupported URL");
+        assert!(matches!(err, DownloadError::Fatal(_)));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_timed_out_is_retryable() {
+        assert!(DownloadError::TimedOut.is_retryable());
+    }
+
+    #[test]
+    fn test_cleanup_partial_files_removes_matching_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::File::create(dir.path().join("abc123.part")).unwrap();
+        fs::File::create(dir.path().join("abc123.info.json")).unwrap();
+        fs::File::create(dir.path().join("other.mp4")).unwrap();
+
+        cleanup_partial_files(dir.path().to_str().unwrap(), "abc123");
+
+        let remaining: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(remaining, vec!["other.mp4".to_string()]);
+    }
+
     #[test]
     fn test_find_downloaded_file_matches_prefix() {
         let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,174 @@
+/// Bootstraps and self-updates the yt-dlp binary, so the service doesn't
+/// depend on yt-dlp already being provisioned on `PATH`.
+///
+/// LEARNING: this mirrors the `download_yt_dlp` helper the `youtube_dl`
+/// crate ships in its optional `downloader` module — fetch the latest
+/// release binary and point the config at it, instead of assuming one
+/// was baked into the image ahead of time.
+use anyhow::{bail, Context, Result};
+use log::info;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::Config;
+
+const YTDLP_RELEASE_BASE: &str = "https://github.com/yt-dlp/yt-dlp/releases/latest/download";
+
+/// Standalone binary asset for this platform. This bundles its own Python
+/// runtime, unlike the bare `yt-dlp` zipapp — which still needs `python3`
+/// on `PATH` and so doesn't actually help in the minimal containers this
+/// bootstrap exists for.
+#[cfg(target_os = "linux")]
+const YTDLP_ASSET: &str = "yt-dlp_linux";
+#[cfg(target_os = "macos")]
+const YTDLP_ASSET: &str = "yt-dlp_macos";
+#[cfg(windows)]
+const YTDLP_ASSET: &str = "yt-dlp.exe";
+
+/// yt-dlp publishes a `SHA2-256SUMS` file alongside every release, listing
+/// the checksum of each asset — fetched and checked before we trust a
+/// downloaded binary enough to chmod +x and exec it.
+const YTDLP_CHECKSUMS_ASSET: &str = "SHA2-256SUMS";
+
+/// How long the GitHub fetch may take before we give up, so a stalled
+/// connection can't hang startup forever — `main.rs` falls back to the
+/// configured `executable_path` when this returns `Err`.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Download the latest yt-dlp release binary into `config.download_dir`
+/// and make it executable. Returns the path to use as
+/// `YtdlpConfig::executable_path`. Safe to call on every startup — it
+/// always re-fetches and overwrites, so it doubles as a self-update.
+pub async fn ensure_ytdlp(config: &Config) -> Result<PathBuf> {
+    std::fs::create_dir_all(&config.download_dir)
+        .context("Failed to create download_dir for yt-dlp binary")?;
+    let path = Path::new(&config.download_dir).join("yt-dlp");
+
+    info!("Fetching latest yt-dlp release into {}", path.display());
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .context("Failed to build yt-dlp fetch client")?;
+
+    let bytes = fetch_asset(&client, YTDLP_ASSET).await?;
+    let checksums = fetch_asset(&client, YTDLP_CHECKSUMS_ASSET).await?;
+    verify_checksum(&bytes, &checksums)?;
+
+    tokio::fs::write(&path, &bytes)
+        .await
+        .context("Failed to write yt-dlp binary")?;
+    make_executable(&path)?;
+
+    info!("yt-dlp bootstrapped at {}", path.display());
+    Ok(path)
+}
+
+async fn fetch_asset(client: &reqwest::Client, name: &str) -> Result<Vec<u8>> {
+    let url = format!("{}/{}", YTDLP_RELEASE_BASE, name);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", name))?
+        .error_for_status()
+        .with_context(|| format!("{} download returned an error status", name))?;
+    Ok(response
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to read {} body", name))?
+        .to_vec())
+}
+
+/// Check `bytes`' SHA-256 against the line for `YTDLP_ASSET` in yt-dlp's
+/// published `SHA2-256SUMS` file, so a compromised release artifact or CDN
+/// doesn't get silently chmod +x'd and executed as a subprocess.
+fn verify_checksum(bytes: &[u8], checksums: &[u8]) -> Result<()> {
+    let checksums = String::from_utf8_lossy(checksums);
+    let expected = checksums
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next()?.trim_start_matches('*');
+            (name == YTDLP_ASSET).then_some(hash)
+        })
+        .with_context(|| format!("No checksum entry for {} in SHA2-256SUMS", YTDLP_ASSET))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    let actual = format!("{:x}", hasher.finalize());
+
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!(
+            "yt-dlp checksum mismatch for {}: expected {}, got {}",
+            YTDLP_ASSET,
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = std::fs::metadata(path)
+        .context("Failed to read yt-dlp binary metadata")?
+        .permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(path, perms).context("Failed to make yt-dlp binary executable")
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_make_executable_sets_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("yt-dlp");
+        std::fs::write(&path, b"fake binary").unwrap();
+
+        make_executable(&path).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn test_verify_checksum_accepts_matching_hash() {
+        let bytes = b"fake binary";
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        let hash = format!("{:x}", hasher.finalize());
+        let checksums = format!("{}  {}\n", hash, YTDLP_ASSET);
+
+        verify_checksum(bytes, checksums.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_checksum_rejects_mismatched_hash() {
+        let checksums = format!("{}  {}\n", "0".repeat(64), YTDLP_ASSET);
+
+        let err = verify_checksum(b"fake binary", checksums.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn test_verify_checksum_rejects_missing_entry() {
+        let checksums = format!("{}  some-other-asset\n", "0".repeat(64));
+
+        let err = verify_checksum(b"fake binary", checksums.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("No checksum entry"));
+    }
+}
@@ -13,26 +13,48 @@
 /// - Pattern matching with match
 mod config;
 mod download;
+mod downloader;
 mod models;
 
 use anyhow::Result;
 use futures::StreamExt;
 use log::{error, info, warn};
+use rand::Rng;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Semaphore;
 
 use config::Config;
 use models::{DownloadRequest, DownloadResult};
 
+/// Starting point for the exponential backoff between retries.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Upper bound on the backoff delay, so a long retry budget doesn't end up
+/// waiting literal minutes between attempts.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
 /// LEARNING: #[tokio::main] transforms main() into an async function.
 /// Tokio is the async runtime — it manages the event loop, like asyncio in Python.
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
-    let config = Arc::new(Config::from_env());
+    let mut config = Config::from_env();
     info!("Starting downloader service");
 
+    if config.auto_update_ytdlp {
+        match downloader::ensure_ytdlp(&config).await {
+            Ok(path) => config.ytdlp.executable_path = path.to_string_lossy().into_owned(),
+            Err(e) => warn!(
+                "Failed to bootstrap yt-dlp, falling back to configured path: {}",
+                e
+            ),
+        }
+    }
+
+    let config = Arc::new(config);
+
     // Create download directory
     std::fs::create_dir_all(&config.download_dir)?;
 
@@ -76,6 +98,18 @@ async fn main() -> Result<()> {
         // LEARNING: `tokio::spawn` is like `asyncio.create_task()` — it runs
         // the future concurrently without blocking the main loop.
         tokio::spawn(async move {
+            // Wait out upcoming livestreams/premieres before taking a
+            // concurrency permit, so a scheduled stream doesn't block other
+            // downloads for however long it is until it goes live.
+            download::wait_for_scheduled_start(
+                &request.video_id,
+                &request.title,
+                &request.url,
+                &config,
+                &client,
+            )
+            .await;
+
             // LEARNING: `.acquire_owned()` waits until a permit is available,
             // enforcing our max concurrent downloads limit. The permit is
             // automatically released when `_permit` is dropped (RAII pattern).
@@ -84,7 +118,7 @@ async fn main() -> Result<()> {
                 Err(_) => return,
             };
 
-            let result = process_download(&request, &config).await;
+            let result = process_download(&request, &config, &client).await;
 
             // Publish result to NATS
             match serde_json::to_vec(&result) {
@@ -105,39 +139,75 @@ async fn main() -> Result<()> {
 ///
 /// LEARNING: `&` means "borrow" — we're reading the data without taking ownership.
 /// The original data stays valid. This is Rust's core memory safety mechanism.
-async fn process_download(request: &DownloadRequest, config: &Config) -> DownloadResult {
-    match download::download_video(&request.video_id, &request.url, config).await {
-        Ok(output) => {
-            let size = download::get_file_size(&output.file_path).unwrap_or(0);
-            info!("Downloaded {} — {} bytes", request.video_id, size);
-            let mut result = DownloadResult::success(request, output.file_path, size);
-
-            // Enrich with yt-dlp metadata when the request has placeholder values
-            // (e.g. admin DM downloads only have video_id as title)
-            let meta = output.metadata;
-            if let Some(t) = meta.title {
-                if request.title == request.video_id {
-                    result.title = t;
+async fn process_download(
+    request: &DownloadRequest,
+    config: &Config,
+    nats_client: &async_nats::Client,
+) -> DownloadResult {
+    let mut attempt = 0;
+
+    loop {
+        match download::download_video(
+            &request.video_id,
+            &request.url,
+            config,
+            request.format.as_deref(),
+            request.audio_only.unwrap_or(false),
+            nats_client,
+        )
+        .await
+        {
+            Ok(output) => {
+                let size = download::get_file_size(&output.file_path).unwrap_or(0);
+                info!("Downloaded {} — {} bytes", request.video_id, size);
+                let mut result = DownloadResult::success(request, output.file_path, size);
+
+                // Enrich with yt-dlp metadata when the request has placeholder values
+                // (e.g. admin DM downloads only have video_id as title)
+                let meta = output.metadata;
+                if let Some(t) = meta.title {
+                    if request.title == request.video_id {
+                        result.title = t;
+                    }
                 }
+                if request.channel.is_none() {
+                    result.channel = meta.channel;
+                }
+                if request.channel_id.is_none() {
+                    result.channel_id = meta.channel_id;
+                }
+                if request.duration.is_none() {
+                    result.duration = meta.duration;
+                }
+                if request.thumbnail.is_none() {
+                    result.thumbnail = meta.thumbnail;
+                }
+
+                return result;
             }
-            if request.channel.is_none() {
-                result.channel = meta.channel;
-            }
-            if request.channel_id.is_none() {
-                result.channel_id = meta.channel_id;
-            }
-            if request.duration.is_none() {
-                result.duration = meta.duration;
+            Err(e) if e.is_retryable() && attempt < config.max_retries => {
+                attempt += 1;
+                let delay = retry_backoff(attempt);
+                warn!(
+                    "Retryable error downloading {} (attempt {}/{}): {} — retrying in {:?}",
+                    request.video_id, attempt, config.max_retries, e, delay
+                );
+                tokio::time::sleep(delay).await;
             }
-            if request.thumbnail.is_none() {
-                result.thumbnail = meta.thumbnail;
+            Err(e) => {
+                error!("Download failed for {}: {}", request.video_id, e);
+                return DownloadResult::failure(request, e.to_string());
             }
-
-            result
-        }
-        Err(e) => {
-            error!("Download failed for {}: {}", request.video_id, e);
-            DownloadResult::failure(request, e.to_string())
         }
     }
 }
+
+/// Exponential backoff with jitter: `base * 2^attempt`, capped, then a
+/// random delay in the first half of that cap so retries from concurrent
+/// tasks don't all land on the same instant.
+fn retry_backoff(attempt: u32) -> Duration {
+    let exponential = RETRY_BASE_DELAY.saturating_mul(1u32 << attempt.min(16));
+    let capped_ms = exponential.min(RETRY_MAX_DELAY).as_millis() as u64;
+    let jitter_ms = rand::thread_rng().gen_range(0..=capped_ms / 2);
+    Duration::from_millis(capped_ms / 2 + jitter_ms)
+}
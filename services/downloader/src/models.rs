@@ -17,6 +17,10 @@ pub struct DownloadRequest {
     pub channel_id: Option<String>,
     pub duration: Option<String>,
     pub thumbnail: Option<String>,
+    /// Per-request override of `YtdlpConfig::format` (e.g. a 4K profile).
+    pub format: Option<String>,
+    /// When set, download audio only regardless of `format`.
+    pub audio_only: Option<bool>,
 }
 
 /// Outgoing result published to NATS (telegram-client consumes these)
@@ -35,6 +39,29 @@ pub struct DownloadResult {
     pub thumbnail: Option<String>,
 }
 
+/// Published when a download is held back because the content is an
+/// upcoming livestream/premiere (telegram-client can tell the user when
+/// it'll actually start downloading).
+#[derive(Debug, Serialize)]
+pub struct DownloadScheduled {
+    pub video_id: String,
+    pub title: String,
+    /// Unix timestamp yt-dlp reported as the scheduled start time.
+    pub scheduled_start: i64,
+}
+
+/// Progress update published while a download is in flight
+/// (telegram-client uses these to edit its "downloading..." message).
+#[derive(Debug, Serialize)]
+pub struct DownloadProgress {
+    pub video_id: String,
+    pub downloaded_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub percent: Option<f64>,
+    pub speed: Option<f64>,
+    pub eta: Option<u64>,
+}
+
 /// LEARNING: `impl` blocks add methods to a struct. Rust doesn't have
 /// class constructors — you just write functions that return the struct.
 impl DownloadResult {
@@ -84,12 +111,15 @@ mod tests {
             channel_id: Some("UC123".into()),
             duration: Some("3:45".into()),
             thumbnail: None,
+            format: None,
+            audio_only: None,
         }
     }
 
     #[test]
     fn test_download_request_deserialize() {
-        let json = r#"{"video_id":"abc123","title":"Test","url":"https://youtube.com/watch?v=abc123"}"#;
+        let json =
+            r#"{"video_id":"abc123","title":"Test","url":"https://youtube.com/watch?v=abc123"}"#;
         let req: DownloadRequest = serde_json::from_str(json).unwrap();
         assert_eq!(req.video_id, "abc123");
         assert_eq!(req.title, "Test");
@@ -104,6 +134,14 @@ mod tests {
         assert_eq!(req.duration, Some("3:45".into()));
     }
 
+    #[test]
+    fn test_download_request_with_format_override() {
+        let json = r#"{"video_id":"abc123","title":"Test","url":"https://youtube.com/watch?v=abc123","format":"bestaudio/best","audio_only":true}"#;
+        let req: DownloadRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.format, Some("bestaudio/best".into()));
+        assert_eq!(req.audio_only, Some(true));
+    }
+
     #[test]
     fn test_download_request_missing_field() {
         let json = r#"{"video_id":"abc123","title":"Test"}"#;
@@ -150,4 +188,31 @@ mod tests {
         assert!(json.contains("\"file_path\":null"));
         assert!(json.contains("\"success\":false"));
     }
+
+    #[test]
+    fn test_download_scheduled_serializes_to_json() {
+        let scheduled = DownloadScheduled {
+            video_id: "abc123".into(),
+            title: "Upcoming premiere".into(),
+            scheduled_start: 1_700_000_000,
+        };
+        let json = serde_json::to_string(&scheduled).unwrap();
+        assert!(json.contains("\"video_id\":\"abc123\""));
+        assert!(json.contains("\"scheduled_start\":1700000000"));
+    }
+
+    #[test]
+    fn test_download_progress_serializes_to_json() {
+        let progress = DownloadProgress {
+            video_id: "abc123".into(),
+            downloaded_bytes: Some(1024),
+            total_bytes: Some(4096),
+            percent: Some(25.0),
+            speed: Some(512.0),
+            eta: Some(6),
+        };
+        let json = serde_json::to_string(&progress).unwrap();
+        assert!(json.contains("\"video_id\":\"abc123\""));
+        assert!(json.contains("\"percent\":25.0"));
+    }
 }